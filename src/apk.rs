@@ -0,0 +1,93 @@
+//! Alpine `apk` package database parsing.
+//!
+//! The `installed` database lists one record per package using
+//! single-letter field prefixes (`P:` package, `V:` version, `D:`
+//! space-separated dependencies), with records separated by a blank
+//! line.
+
+use super::ast::{Document, Library, Package};
+use super::source::{PackageSource, ParseError, ParseResult};
+
+/// Parses Alpine `apk` `installed` databases into the common AST.
+#[derive(Debug, Default)]
+pub struct ApkParser;
+
+impl PackageSource for ApkParser {
+    fn parse(&self, source: &str) -> ParseResult<Document> {
+        let mut packages = Vec::new();
+
+        for record in source.split("\n\n") {
+            let record = record.trim();
+
+            if !record.is_empty() {
+                packages.push(parse_record(record)?);
+            }
+        }
+
+        Ok(Document::new("alpine".to_string(), packages))
+    }
+}
+
+fn parse_record(record: &str) -> ParseResult<Package> {
+    let mut name = None;
+    let mut version = String::new();
+    let mut depends = Vec::new();
+
+    for line in record.lines() {
+        if let Some(value) = line.strip_prefix("P:") {
+            name = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("V:") {
+            version = value.to_string();
+        } else if let Some(value) = line.strip_prefix("D:") {
+            depends = value
+                .split_whitespace()
+                .map(|package| Library {
+                    package: package.to_string(),
+                    ..Default::default()
+                })
+                .collect();
+        }
+    }
+
+    let name = name.ok_or_else(|| ParseError::PackageNameNotFound(record.to_string()))?;
+
+    Ok(Package {
+        name,
+        version,
+        depends,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let source = "\
+P:musl
+V:1.2.3-r4
+D:so:libc.musl-x86_64.so.1
+
+P:busybox
+V:1.35.0-r17
+D:musl so:libc.musl-x86_64.so.1";
+
+        let result = ApkParser.parse(source).unwrap();
+
+        assert_eq!(result.packages.len(), 2);
+        assert_eq!(result.packages[0].name, "musl");
+        assert_eq!(result.packages[0].version, "1.2.3-r4");
+        assert_eq!(result.packages[1].name, "busybox");
+        assert_eq!(result.packages[1].depends.len(), 2);
+        assert_eq!(result.packages[1].depends[0].package, "musl");
+    }
+
+    #[test]
+    fn test_parse_missing_package_name() {
+        let result = ApkParser.parse("V:1.2.3-r4");
+
+        assert!(result.is_err());
+    }
+}
@@ -1,3 +1,6 @@
+use crate::version::RelOp;
+use serde::{Deserialize, Serialize};
+
 /// Node kind enumerable.
 #[derive(Debug)]
 pub enum NodeKind {
@@ -6,56 +9,187 @@ pub enum NodeKind {
     Library,
 }
 
+fn document_kind() -> NodeKind {
+    NodeKind::Document
+}
+
+fn package_kind() -> NodeKind {
+    NodeKind::Package
+}
+
+fn library_kind() -> NodeKind {
+    NodeKind::Library
+}
+
 /// Describes a document node.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Document {
+    #[serde(skip, default = "document_kind")]
     pub kind: NodeKind,
+    /// The distro namespace the packages were parsed from, e.g.
+    /// `"debian"` or `"alpine"`. Scopes vulnerability matching to
+    /// advisories for the same distro family.
+    pub namespace: String,
     pub packages: Vec<Package>,
 }
 
 impl Document {
-    pub fn new(packages: Vec<Package>) -> Document {
+    pub fn new(namespace: String, packages: Vec<Package>) -> Document {
         Document {
             kind: NodeKind::Document,
+            namespace,
             packages,
         }
     }
 }
 
 /// Describes a package node.
-#[derive(Debug)]
+///
+/// `depends`, `pre_depends`, `recommends`, `suggests`, `enhances`,
+/// `breaks`, `conflicts`, `replaces` and `provides` are all
+/// `Vec<Library>`, so they're built via struct literal rather than a
+/// positional constructor — a transposed pair (e.g. `breaks` for
+/// `conflicts`) would compile silently otherwise. [`Package::default`]
+/// covers the relationship fields a caller isn't setting.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Package {
+    #[serde(skip, default = "package_kind")]
     pub kind: NodeKind,
     pub name: String,
+    pub version: String,
     pub description: String,
     pub depends: Vec<Library>,
+    pub pre_depends: Vec<Library>,
+    pub recommends: Vec<Library>,
+    pub suggests: Vec<Library>,
+    pub enhances: Vec<Library>,
+    pub breaks: Vec<Library>,
+    pub conflicts: Vec<Library>,
+    pub replaces: Vec<Library>,
+    pub provides: Vec<Library>,
 }
 
-impl Package {
-    pub fn new(name: String, description: String, depends: Vec<Library>) -> Package {
+impl Default for Package {
+    fn default() -> Package {
         Package {
             kind: NodeKind::Package,
-            name,
-            description,
-            depends,
+            name: String::new(),
+            version: String::new(),
+            description: String::new(),
+            depends: Vec::new(),
+            pre_depends: Vec::new(),
+            recommends: Vec::new(),
+            suggests: Vec::new(),
+            enhances: Vec::new(),
+            breaks: Vec::new(),
+            conflicts: Vec::new(),
+            replaces: Vec::new(),
+            provides: Vec::new(),
         }
     }
 }
 
 /// Describes a library node (e.g. a dependency).
-#[derive(Debug)]
+///
+/// `package` and `constraint` come from a single relationship atom such
+/// as `libc6 (>= 2.14)`; `alternates` holds the atoms following it when
+/// the field lists them separated by ` | `, e.g. `debconf (>= 0.5) |
+/// debconf-2.0`.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Library {
+    #[serde(skip, default = "library_kind")]
     pub kind: NodeKind,
-    pub name: String,
-    pub alternates: Vec<String>,
+    pub package: String,
+    pub arch_qualifier: Option<String>,
+    pub constraint: Option<(RelOp, String)>,
+    pub alternates: Vec<Library>,
 }
 
-impl Library {
-    pub fn new(name: String, alternates: Vec<String>) -> Library {
+impl Default for Library {
+    fn default() -> Library {
         Library {
             kind: NodeKind::Library,
-            name,
-            alternates,
+            package: String::new(),
+            arch_qualifier: None,
+            constraint: None,
+            alternates: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_library() -> Library {
+        Library {
+            package: "debconf".to_string(),
+            constraint: Some((RelOp::GreaterOrEqual, "0.5".to_string())),
+            alternates: vec![Library {
+                package: "debconf-2.0".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn sample_package() -> Package {
+        Package {
+            name: "libssl1.0.0".to_string(),
+            version: "1.0.1-4ubuntu5.5".to_string(),
+            description: "SSL shared libraries".to_string(),
+            depends: vec![sample_library()],
+            ..Default::default()
         }
     }
+
+    #[test]
+    fn test_library_round_trips_through_json() {
+        let library = sample_library();
+
+        let json = serde_json::to_string(&library).unwrap();
+        let restored: Library = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.package, library.package);
+        assert_eq!(restored.constraint, library.constraint);
+        assert_eq!(restored.alternates.len(), 1);
+        assert_eq!(restored.alternates[0].package, "debconf-2.0");
+        assert!(matches!(restored.kind, NodeKind::Library));
+    }
+
+    #[test]
+    fn test_constraint_serializes_as_a_two_element_array() {
+        let json = serde_json::to_value(sample_library()).unwrap();
+
+        assert_eq!(
+            json["constraint"],
+            serde_json::json!(["GreaterOrEqual", "0.5"])
+        );
+    }
+
+    #[test]
+    fn test_package_round_trips_through_json() {
+        let package = sample_package();
+
+        let json = serde_json::to_string(&package).unwrap();
+        let restored: Package = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.name, package.name);
+        assert_eq!(restored.version, package.version);
+        assert_eq!(restored.depends.len(), 1);
+        assert_eq!(restored.depends[0].alternates.len(), 1);
+        assert!(matches!(restored.kind, NodeKind::Package));
+    }
+
+    #[test]
+    fn test_document_round_trips_through_json() {
+        let document = Document::new("debian".to_string(), vec![sample_package()]);
+
+        let json = serde_json::to_string(&document).unwrap();
+        let restored: Document = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.packages.len(), 1);
+        assert_eq!(restored.packages[0].name, "libssl1.0.0");
+        assert!(matches!(restored.kind, NodeKind::Document));
+    }
 }
@@ -1,23 +1,104 @@
 //! # Debian package parser
 //!
-//! A library for parsing files that describe Debian packages.
+//! A library for parsing files that describe installed packages across
+//! distro families (dpkg, apk, ...).
 
+pub mod apk;
 pub mod ast;
 pub mod parser;
+pub mod source;
+pub mod version;
+pub mod vulnerability;
 
+use parser::DpkgParser;
+use source::PackageSource;
 use std::{error, fmt, fs, io};
 
 /// Runs the application.
 pub fn run(config: Config) -> Result<(), Box<dyn error::Error>> {
     let contents = read_file(&config.file_path[..])?;
+    let contents = contents.trim();
 
-    let parsed = parser::parse(contents.trim())?;
+    let format = config.format.unwrap_or_else(|| Format::detect(contents));
+    let source: Box<dyn PackageSource> = match format {
+        Format::Dpkg => Box::new(DpkgParser),
+        Format::Apk => Box::new(apk::ApkParser),
+    };
 
-    println!("parsed: {:#?}", parsed);
+    let parsed = source.parse(contents)?;
+
+    println!("{}", render(&parsed, config.output_format)?);
 
     Ok(())
 }
 
+/// Renders a parsed document as requested by `format`.
+fn render(document: &ast::Document, format: OutputFormat) -> Result<String, Box<dyn error::Error>> {
+    Ok(match format {
+        OutputFormat::Debug => format!("parsed: {:#?}", document),
+        OutputFormat::Json => serde_json::to_string_pretty(document)?,
+        OutputFormat::NdJson => document
+            .packages
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<serde_json::Result<Vec<_>>>()?
+            .join("\n"),
+    })
+}
+
+/// Identifies which package database format to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dpkg,
+    Apk,
+}
+
+impl Format {
+    /// Parses a format name as given on the command line, e.g. via
+    /// `--format=apk`.
+    fn parse(s: &str) -> Option<Format> {
+        match s {
+            "dpkg" => Some(Format::Dpkg),
+            "apk" => Some(Format::Apk),
+            _ => None,
+        }
+    }
+
+    /// Detects the format from the file contents: an apk database
+    /// starts each record with a `P:` package field, while a dpkg
+    /// database starts it with a `Package:` field.
+    fn detect(contents: &str) -> Format {
+        match contents.lines().next() {
+            Some(line) if line.starts_with("P:") => Format::Apk,
+            _ => Format::Dpkg,
+        }
+    }
+}
+
+/// Identifies how a parsed [`ast::Document`] should be printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Pretty-printed `{:#?}` debug output.
+    Debug,
+    /// A single pretty-printed JSON document.
+    Json,
+    /// One JSON object per package, newline-delimited.
+    NdJson,
+}
+
+impl OutputFormat {
+    /// Parses an output format name as given on the command line, e.g.
+    /// via `--output=ndjson`.
+    fn parse(s: &str) -> Option<OutputFormat> {
+        match s {
+            "debug" => Some(OutputFormat::Debug),
+            "json" => Some(OutputFormat::Json),
+            "ndjson" => Some(OutputFormat::NdJson),
+            _ => None,
+        }
+    }
+}
+
 /// Describes an error that may occur when reading a file.
 #[derive(Debug, Clone)]
 pub enum ReadFileError {
@@ -54,6 +135,11 @@ fn read_file(file_path: &str) -> Result<String, ReadFileError> {
 /// Describes the application's configuration.
 pub struct Config {
     pub file_path: String,
+    /// The package database format to parse, or `None` to auto-detect
+    /// it from the file contents.
+    pub format: Option<Format>,
+    /// How the parsed document should be printed.
+    pub output_format: OutputFormat,
 }
 
 /// Describes an error that may occur when building the configuration.
@@ -82,6 +168,90 @@ impl Config {
             None => return Err(BuildConfigError::NoFilePath),
         };
 
-        Ok(Config { file_path })
+        let mut format = None;
+        let mut output_format = OutputFormat::Debug;
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--format=") {
+                format = Format::parse(value);
+            } else if let Some(value) = arg.strip_prefix("--output=") {
+                if let Some(parsed) = OutputFormat::parse(value) {
+                    output_format = parsed;
+                }
+            }
+        }
+
+        Ok(Config {
+            file_path,
+            format,
+            output_format,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::{Document, Package};
+
+    fn sample_document() -> Document {
+        Document::new(
+            "debian".to_string(),
+            vec![
+                Package {
+                    name: "libc6".to_string(),
+                    version: "2.31-0ubuntu9".to_string(),
+                    description: "GNU C Library".to_string(),
+                    ..Default::default()
+                },
+                Package {
+                    name: "zlib1g".to_string(),
+                    version: "1:1.2.11.dfsg-2ubuntu1".to_string(),
+                    description: "compression library".to_string(),
+                    ..Default::default()
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn test_build_parses_format_and_output_flags() {
+        let args = vec![
+            "dpkg_parser".to_string(),
+            "status".to_string(),
+            "--format=apk".to_string(),
+            "--output=json".to_string(),
+        ];
+
+        let config = Config::build(args.into_iter()).unwrap();
+
+        assert_eq!(config.format, Some(Format::Apk));
+        assert_eq!(config.output_format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_build_defaults_to_auto_detect_and_debug_output() {
+        let args = vec!["dpkg_parser".to_string(), "status".to_string()];
+
+        let config = Config::build(args.into_iter()).unwrap();
+
+        assert_eq!(config.format, None);
+        assert_eq!(config.output_format, OutputFormat::Debug);
+    }
+
+    #[test]
+    fn test_render_json_contains_every_package() {
+        let output = render(&sample_document(), OutputFormat::Json).unwrap();
+
+        assert!(output.contains("\"name\": \"libc6\""));
+        assert!(output.contains("\"name\": \"zlib1g\""));
+    }
+
+    #[test]
+    fn test_render_ndjson_emits_one_line_per_package() {
+        let output = render(&sample_document(), OutputFormat::NdJson).unwrap();
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(serde_json::from_str::<Package>(lines[0]).is_ok());
     }
 }
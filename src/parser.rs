@@ -1,5 +1,10 @@
 use super::ast::*;
-use std::{error, fmt, io::Write, str::from_utf8};
+use super::source::{PackageSource, ParseError, ParseResult};
+use super::version::RelOp;
+use std::{
+    io::{BufRead, Write},
+    str::from_utf8,
+};
 
 #[derive(Debug, Clone)]
 enum FieldName {
@@ -12,12 +17,16 @@ enum FieldName {
     // Maintainer,
     // Architecture,
     // Source,
-    // Version,
-    // Replaces,
-    // Provides,
+    Version,
     Depends,
-    // Suggests,
-    // Conflicts,
+    PreDepends,
+    Recommends,
+    Suggests,
+    Enhances,
+    Breaks,
+    Conflicts,
+    Replaces,
+    Provides,
     Description,
     // OriginalMaintainer,
     // Homepage,
@@ -36,12 +45,16 @@ impl ToString for FieldName {
             // FieldName::Maintainer => String::from("Maintainer"),
             // FieldName::Architecture => String::from("Architecture"),
             // FieldName::Source => String::from("Source"),
-            // FieldName::Version => String::from("Version"),
-            // FieldName::Replaces => String::from("Replaces"),
-            // FieldName::Provides => String::from("Provides"),
+            FieldName::Version => String::from("Version"),
             FieldName::Depends => String::from("Depends"),
-            // FieldName::Suggests => String::from("Suggests"),
-            // FieldName::Conflicts => String::from("Conflicts"),
+            FieldName::PreDepends => String::from("Pre-Depends"),
+            FieldName::Recommends => String::from("Recommends"),
+            FieldName::Suggests => String::from("Suggests"),
+            FieldName::Enhances => String::from("Enhances"),
+            FieldName::Breaks => String::from("Breaks"),
+            FieldName::Conflicts => String::from("Conflicts"),
+            FieldName::Replaces => String::from("Replaces"),
+            FieldName::Provides => String::from("Provides"),
             FieldName::Description => String::from("Description"),
             // FieldName::OriginalMaintainer => String::from("Original-Maintainer"),
             // FieldName::Homepage => String::from("Homepage"),
@@ -50,33 +63,14 @@ impl ToString for FieldName {
     }
 }
 
-/// A result from a parsing operation.
-type ParseResult<T> = Result<T, ParseError>;
+/// Parses Debian `dpkg` package databases (`status`, `Packages`) into the
+/// common AST.
+#[derive(Debug, Default)]
+pub struct DpkgParser;
 
-/// Describes an error that may occur when parsing a source string.
-#[derive(Debug, Clone)]
-pub enum ParseError {
-    PackageNameNotFound(String),
-}
-
-impl ParseError {
-    fn write_error(f: &mut fmt::Formatter<'_>, error: &str, source: &str) -> fmt::Result {
-        writeln!(f, "{}", error)?;
-        writeln!(f)?;
-        write!(f, "{}", source)?;
-        Ok(())
-    }
-}
-
-impl error::Error for ParseError {}
-
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ParseError::PackageNameNotFound(s) => {
-                ParseError::write_error(f, "package name not found", s)
-            }
-        }
+impl PackageSource for DpkgParser {
+    fn parse(&self, source: &str) -> ParseResult<Document> {
+        parse(source)
     }
 }
 
@@ -113,31 +107,56 @@ impl fmt::Display for ParseError {
 /// ```
 pub fn parse(source: &str) -> ParseResult<Document> {
     let mut packages = Vec::new();
-    let mut buf = Vec::new();
 
-    // append an empty line to the end
-    writeln!(&mut buf, "{}", source).unwrap();
-    writeln!(&mut buf).unwrap();
-    let source = from_utf8(&buf[..]).unwrap();
+    for package in PackageIter::new(source.as_bytes()) {
+        packages.push(package?);
+    }
 
-    let mut buf = Vec::new();
+    Ok(Document::new("debian".to_string(), packages))
+}
 
-    for line in source.lines() {
-        if !line.is_empty() {
-            writeln!(&mut buf, "{}", line).unwrap();
-        } else {
-            let s = from_utf8(&buf[..]).unwrap();
-            match parse_package(s) {
-                Ok(package) => {
-                    packages.push(package);
-                    buf.clear();
+/// Iterates over the packages in a `BufRead` source one record at a
+/// time, without materializing the whole file or the whole `Document`
+/// in memory. Records are separated by a blank line, same as a dpkg
+/// `status` or `Packages` file.
+pub struct PackageIter<R> {
+    reader: R,
+}
+
+impl<R: BufRead> PackageIter<R> {
+    pub fn new(reader: R) -> PackageIter<R> {
+        PackageIter { reader }
+    }
+}
+
+impl<R: BufRead> Iterator for PackageIter<R> {
+    type Item = ParseResult<Package>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = String::new();
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+
+            match self.reader.read_line(&mut line) {
+                Ok(0) => {
+                    return if record.trim().is_empty() {
+                        None
+                    } else {
+                        Some(parse_package(&record))
+                    };
                 }
-                Err(err) => return Err(err),
+                Ok(_) if line.trim().is_empty() => {
+                    if !record.trim().is_empty() {
+                        return Some(parse_package(&record));
+                    }
+                }
+                Ok(_) => record.push_str(&line),
+                Err(err) => return Some(Err(ParseError::Io(err.to_string()))),
             }
         }
     }
-
-    Ok(Document::new(packages))
 }
 
 fn parse_package(source: &str) -> ParseResult<Package> {
@@ -147,11 +166,40 @@ fn parse_package(source: &str) -> ParseResult<Package> {
         return Err(ParseError::PackageNameNotFound(source.to_string()));
     }
 
+    let version = parse_field(FieldName::Version, source).unwrap();
     let description = parse_field(FieldName::Description, source).unwrap();
-    let depends = parse_field(FieldName::Depends, source).unwrap();
-    let depends = parse_libraries(&depends).unwrap();
+    let depends = parse_relationship(FieldName::Depends, source)?;
+    let pre_depends = parse_relationship(FieldName::PreDepends, source)?;
+    let recommends = parse_relationship(FieldName::Recommends, source)?;
+    let suggests = parse_relationship(FieldName::Suggests, source)?;
+    let enhances = parse_relationship(FieldName::Enhances, source)?;
+    let breaks = parse_relationship(FieldName::Breaks, source)?;
+    let conflicts = parse_relationship(FieldName::Conflicts, source)?;
+    let replaces = parse_relationship(FieldName::Replaces, source)?;
+    let provides = parse_relationship(FieldName::Provides, source)?;
+
+    Ok(Package {
+        name,
+        version,
+        description,
+        depends,
+        pre_depends,
+        recommends,
+        suggests,
+        enhances,
+        breaks,
+        conflicts,
+        replaces,
+        provides,
+        ..Default::default()
+    })
+}
 
-    Ok(Package::new(name, description, depends))
+/// Parses a relationship field (e.g. `Depends`, `Breaks`) into its
+/// constituent libraries.
+fn parse_relationship(field_name: FieldName, source: &str) -> ParseResult<Vec<Library>> {
+    let field = parse_field(field_name, source)?;
+    parse_libraries(&field)
 }
 
 fn parse_field(field_name: FieldName, source: &str) -> ParseResult<String> {
@@ -187,17 +235,27 @@ fn parse_libraries(source: &str) -> ParseResult<Vec<Library>> {
         libraries = source
             .split(", ")
             .map(|s| {
-                let vec = s.split(" | ").collect::<Vec<&str>>();
-                let name = vec[0].to_string();
-                let alternates = vec[1..].into_iter().map(|s| s.to_string()).fold(
-                    Vec::new(),
-                    |mut acc, value| {
-                        acc.push(value);
-                        acc
-                    },
-                );
-
-                Library::new(name, alternates)
+                let mut atoms = s.split(" | ");
+                let (package, arch_qualifier, constraint) = parse_atom(atoms.next().unwrap());
+                let alternates = atoms
+                    .map(|atom| {
+                        let (package, arch_qualifier, constraint) = parse_atom(atom);
+                        Library {
+                            package,
+                            arch_qualifier,
+                            constraint,
+                            ..Default::default()
+                        }
+                    })
+                    .collect();
+
+                Library {
+                    package,
+                    arch_qualifier,
+                    constraint,
+                    alternates,
+                    ..Default::default()
+                }
             })
             .collect();
     }
@@ -205,6 +263,34 @@ fn parse_libraries(source: &str) -> ParseResult<Vec<Library>> {
     Ok(libraries)
 }
 
+/// Parses a single relationship atom, e.g. `libc6:any (>= 2.14)`, into
+/// its package name, optional arch qualifier and optional version
+/// constraint.
+fn parse_atom(atom: &str) -> (String, Option<String>, Option<(RelOp, String)>) {
+    let atom = atom.trim();
+
+    let (name_part, constraint) = match atom.find('(') {
+        Some(start) => {
+            let end = atom.find(')').unwrap_or(atom.len());
+            let mut parts = atom[start + 1..end].trim().splitn(2, ' ');
+            let op = parts.next().unwrap_or("");
+            let version = parts.next().unwrap_or("").trim().to_string();
+
+            (atom[..start].trim(), RelOp::parse(op).map(|op| (op, version)))
+        }
+        None => (atom, None),
+    };
+
+    let (package, arch_qualifier) = match name_part.split_once(':') {
+        Some((package, qualifier)) if qualifier == "any" || qualifier == "native" => {
+            (package.to_string(), Some(qualifier.to_string()))
+        }
+        _ => (name_part.to_string(), None),
+    };
+
+    (package, arch_qualifier, constraint)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,6 +397,14 @@ Original-Maintainer: Debian OpenSSL Team <pkg-openssl-devel@lists.alioth.debian.
         );
     }
 
+    #[test]
+    fn test_parse_version_field() {
+        assert_eq!(
+            parse_field(FieldName::Version, PACKAGE).unwrap(),
+            "1.0.1-4ubuntu5.5"
+        );
+    }
+
     #[test]
     fn test_parse_depends_field() {
         assert_eq!(
@@ -319,6 +413,35 @@ Original-Maintainer: Debian OpenSSL Team <pkg-openssl-devel@lists.alioth.debian.
         );
     }
 
+    #[test]
+    fn test_parse_pre_depends_field() {
+        assert_eq!(
+            parse_field(FieldName::PreDepends, PACKAGE).unwrap(),
+            "multiarch-support"
+        );
+    }
+
+    #[test]
+    fn test_parse_breaks_field() {
+        assert_eq!(
+            parse_field(FieldName::Breaks, PACKAGE).unwrap(),
+            "openssh-client (<< 1:5.9p1-4), openssh-server (<< 1:5.9p1-4)"
+        );
+    }
+
+    #[test]
+    fn test_parse_package_relationship_fields() {
+        let package = parse_package(PACKAGE).unwrap();
+
+        assert_eq!(package.pre_depends[0].package, "multiarch-support");
+        assert_eq!(package.breaks.len(), 2);
+        assert_eq!(package.breaks[0].package, "openssh-client");
+        assert_eq!(
+            package.breaks[0].constraint,
+            Some((RelOp::StrictlyLess, "1:5.9p1-4".to_string()))
+        );
+    }
+
     #[test]
     fn test_parse_description_field() {
         assert_eq!(
@@ -337,12 +460,75 @@ It is part of the OpenSSL implementation of SSL."
             parse_libraries("libc6 (>= 2.14), zlib1g (>= 1:1.1.4), debconf (>= 0.5) | debconf-2.0")
                 .unwrap();
 
-        assert_eq!(result[0].name, "libc6 (>= 2.14)");
+        assert_eq!(result[0].package, "libc6");
+        assert_eq!(
+            result[0].constraint,
+            Some((RelOp::GreaterOrEqual, "2.14".to_string()))
+        );
         assert!(result[0].alternates.is_empty());
-        assert_eq!(result[1].name, "zlib1g (>= 1:1.1.4)");
-        assert!(result[1].alternates.is_empty());
-        assert_eq!(result[2].name, "debconf (>= 0.5)");
+
+        assert_eq!(result[1].package, "zlib1g");
+        assert_eq!(
+            result[1].constraint,
+            Some((RelOp::GreaterOrEqual, "1:1.1.4".to_string()))
+        );
+
+        assert_eq!(result[2].package, "debconf");
+        assert_eq!(
+            result[2].constraint,
+            Some((RelOp::GreaterOrEqual, "0.5".to_string()))
+        );
         assert_eq!(result[2].alternates.len(), 1);
-        assert_eq!(result[2].alternates[0], "debconf-2.0");
+        assert_eq!(result[2].alternates[0].package, "debconf-2.0");
+        assert!(result[2].alternates[0].constraint.is_none());
+    }
+
+    #[test]
+    fn test_parse_libraries_arch_qualifier() {
+        let result = parse_libraries("libc6:any").unwrap();
+
+        assert_eq!(result[0].package, "libc6");
+        assert_eq!(result[0].arch_qualifier, Some("any".to_string()));
+    }
+
+    #[test]
+    fn test_package_iter_yields_one_package_at_a_time() {
+        let source = "\
+Package: libws-commons-util-java
+Version: 1.0.1-7
+Description: Common utilities from the Apache Web Services Project
+
+Package: python-pkg-resources
+Version: 0.6.24-1ubuntu1
+Description: Package Discovery and Resource Access using pkg_resources
+";
+
+        let mut iter = PackageIter::new(source.as_bytes());
+
+        assert_eq!(iter.next().unwrap().unwrap().name, "libws-commons-util-java");
+        assert_eq!(iter.next().unwrap().unwrap().name, "python-pkg-resources");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_package_iter_without_trailing_blank_line() {
+        let source = "Package: tcpd\nVersion: 7.6.q-21";
+
+        let packages: Vec<Package> = PackageIter::new(source.as_bytes())
+            .collect::<ParseResult<Vec<Package>>>()
+            .unwrap();
+
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "tcpd");
+    }
+
+    #[test]
+    fn test_package_iter_surfaces_io_errors() {
+        let mut source = b"Package: tcpd\nVersion: ".to_vec();
+        source.extend_from_slice(&[0xff, 0xfe]); // invalid UTF-8
+
+        let mut iter = PackageIter::new(&source[..]);
+
+        assert!(matches!(iter.next(), Some(Err(ParseError::Io(_)))));
     }
 }
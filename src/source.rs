@@ -0,0 +1,48 @@
+//! Pluggable package-source parsing.
+//!
+//! A [`PackageSource`] turns the contents of a distro-specific package
+//! database into the common [`Document`](crate::ast::Document) AST, so
+//! callers don't need to know whether they're reading a dpkg `status`
+//! file or an Alpine `apk` `installed` database.
+
+use super::ast::Document;
+use std::{error, fmt};
+
+/// A result from a parsing operation.
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Describes an error that may occur when parsing a source string.
+#[derive(Debug, Clone)]
+pub enum ParseError {
+    PackageNameNotFound(String),
+    /// Reading from the underlying `BufRead` failed, e.g. because the
+    /// source contained invalid UTF-8.
+    Io(String),
+}
+
+impl ParseError {
+    pub(crate) fn write_error(f: &mut fmt::Formatter<'_>, error: &str, source: &str) -> fmt::Result {
+        writeln!(f, "{}", error)?;
+        writeln!(f)?;
+        write!(f, "{}", source)?;
+        Ok(())
+    }
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::PackageNameNotFound(s) => {
+                ParseError::write_error(f, "package name not found", s)
+            }
+            ParseError::Io(message) => write!(f, "failed to read package source: {}", message),
+        }
+    }
+}
+
+/// Parses a package database format into the common AST.
+pub trait PackageSource {
+    fn parse(&self, source: &str) -> ParseResult<Document>;
+}
@@ -0,0 +1,265 @@
+//! Debian version comparison.
+//!
+//! Implements the dpkg version comparison algorithm (Debian Policy
+//! §5.6.12) so that a parsed dependency constraint such as
+//! `libc6 (>= 2.14)` can be checked against an installed version.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// A parsed Debian package version of the form
+/// `[epoch:]upstream_version[-debian_revision]`.
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub epoch: u32,
+    pub upstream_version: String,
+    pub debian_revision: String,
+}
+
+impl Version {
+    /// Parses a raw version string into its epoch, upstream version and
+    /// debian revision parts.
+    ///
+    /// A missing epoch defaults to `0` and a missing revision compares
+    /// as empty, so `1.0` and `1.0-1` are not equal.
+    pub fn parse(s: &str) -> Version {
+        let (epoch, rest) = match s.find(':') {
+            Some(i) => (s[..i].parse().unwrap_or(0), &s[i + 1..]),
+            None => (0, s),
+        };
+
+        let (upstream_version, debian_revision) = match rest.rfind('-') {
+            Some(i) => (rest[..i].to_string(), rest[i + 1..].to_string()),
+            None => (rest.to_string(), String::new()),
+        };
+
+        Version {
+            epoch,
+            upstream_version,
+            debian_revision,
+        }
+    }
+
+    /// Returns whether this version satisfies `op` relative to `other`,
+    /// e.g. `installed.satisfies(RelOp::GreaterOrEqual, &required)`.
+    pub fn satisfies(&self, op: RelOp, other: &Version) -> bool {
+        match self.cmp(other) {
+            Ordering::Less => matches!(op, RelOp::StrictlyLess | RelOp::LessOrEqual),
+            Ordering::Equal => {
+                matches!(op, RelOp::LessOrEqual | RelOp::Equal | RelOp::GreaterOrEqual)
+            }
+            Ordering::Greater => matches!(op, RelOp::GreaterOrEqual | RelOp::StrictlyGreater),
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| compare_part(&self.upstream_version, &other.upstream_version))
+            .then_with(|| compare_part(&self.debian_revision, &other.debian_revision))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// `PartialEq`/`Eq` are derived from `cmp`, not from the raw fields, so
+// that e.g. `1.0` and `1.0-0` compare equal just like `dpkg
+// --compare-versions` treats them: a missing revision and an explicit
+// `-0` revision both compare as the digit run `0`.
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+/// A relational operator used in a dependency version constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelOp {
+    StrictlyLess,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    StrictlyGreater,
+}
+
+impl RelOp {
+    /// Parses the operator as written in a Debian relationship field,
+    /// e.g. `>=` in `libc6 (>= 2.14)`.
+    pub fn parse(s: &str) -> Option<RelOp> {
+        match s {
+            "<<" => Some(RelOp::StrictlyLess),
+            "<=" => Some(RelOp::LessOrEqual),
+            "=" => Some(RelOp::Equal),
+            ">=" => Some(RelOp::GreaterOrEqual),
+            ">>" => Some(RelOp::StrictlyGreater),
+            _ => None,
+        }
+    }
+}
+
+/// Compares one upstream-version-or-revision segment against another
+/// using the dpkg algorithm: alternating non-digit and digit runs, the
+/// former compared character-by-character with [`order_of`], the latter
+/// compared numerically after stripping leading zeros.
+fn compare_part(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        while a.peek().is_some_and(|c| !c.is_ascii_digit())
+            || b.peek().is_some_and(|c| !c.is_ascii_digit())
+        {
+            let ca = if a.peek().is_some_and(|c| !c.is_ascii_digit()) {
+                a.next()
+            } else {
+                None
+            };
+            let cb = if b.peek().is_some_and(|c| !c.is_ascii_digit()) {
+                b.next()
+            } else {
+                None
+            };
+
+            let order = order_of(ca).cmp(&order_of(cb));
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+
+        let mut da = String::new();
+        while a.peek().is_some_and(|c| c.is_ascii_digit()) {
+            da.push(a.next().unwrap());
+        }
+        let mut db = String::new();
+        while b.peek().is_some_and(|c| c.is_ascii_digit()) {
+            db.push(b.next().unwrap());
+        }
+
+        let na = da.trim_start_matches('0');
+        let nb = db.trim_start_matches('0');
+        match na.len().cmp(&nb.len()).then_with(|| na.cmp(nb)) {
+            Ordering::Equal => {}
+            order => return order,
+        }
+
+        if a.peek().is_none() && b.peek().is_none() {
+            return Ordering::Equal;
+        }
+    }
+}
+
+/// Orders a character for the non-digit part of the dpkg comparison:
+/// `~` sorts before everything (even the end of the string), the end
+/// of the string sorts before any real character, letters sort before
+/// all other characters, and ties fall back to byte value.
+fn order_of(c: Option<char>) -> i32 {
+    match c {
+        Some('~') => -1,
+        None => 0,
+        Some(ch) if ch.is_ascii_alphabetic() => 1000 + ch as i32,
+        Some(ch) => 2000 + ch as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_splits_epoch_upstream_and_revision() {
+        let version = Version::parse("1:1.1.4-3");
+
+        assert_eq!(version.epoch, 1);
+        assert_eq!(version.upstream_version, "1.1.4");
+        assert_eq!(version.debian_revision, "3");
+    }
+
+    #[test]
+    fn test_parse_defaults_epoch_and_revision() {
+        let version = Version::parse("2.14");
+
+        assert_eq!(version.epoch, 0);
+        assert_eq!(version.upstream_version, "2.14");
+        assert_eq!(version.debian_revision, "");
+    }
+
+    #[test]
+    fn test_compares_epoch_first() {
+        assert_eq!(
+            Version::parse("1:1.0").cmp(&Version::parse("2.0")),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_compares_upstream_numerically() {
+        assert_eq!(
+            Version::parse("1.10").cmp(&Version::parse("1.9")),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_missing_revision_differs_from_present() {
+        assert_eq!(
+            Version::parse("1.0").cmp(&Version::parse("1.0-1")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_missing_revision_equals_explicit_zero_revision() {
+        // dpkg --compare-versions 1.0-0 eq 1.0 agrees, so == must agree
+        // with cmp() here rather than comparing the raw fields.
+        assert_eq!(Version::parse("1.0"), Version::parse("1.0-0"));
+        assert_eq!(
+            Version::parse("1.0").cmp(&Version::parse("1.0-0")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_tilde_sorts_before_everything() {
+        assert_eq!(
+            Version::parse("1.0~beta1").cmp(&Version::parse("1.0")),
+            Ordering::Less
+        );
+        assert_eq!(
+            Version::parse("1.0~~").cmp(&Version::parse("1.0~")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_letters_sort_before_punctuation() {
+        assert_eq!(
+            Version::parse("1.0a").cmp(&Version::parse("1.0+")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_satisfies() {
+        let installed = Version::parse("2.14");
+        let required = Version::parse("2.10");
+
+        assert!(installed.satisfies(RelOp::GreaterOrEqual, &required));
+        assert!(!installed.satisfies(RelOp::StrictlyLess, &required));
+        assert!(installed.satisfies(RelOp::Equal, &Version::parse("2.14")));
+    }
+
+    #[test]
+    fn test_rel_op_parse() {
+        assert_eq!(RelOp::parse(">="), Some(RelOp::GreaterOrEqual));
+        assert_eq!(RelOp::parse("<<"), Some(RelOp::StrictlyLess));
+        assert_eq!(RelOp::parse("~="), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Vulnerability matching.
+//!
+//! Cross-references a parsed [`Document`] against a security advisory
+//! feed so a caller can report which installed packages are affected
+//! by a known CVE, the same role a container image scanner plays when
+//! it indexes dpkg features against per-distro CVE data.
+
+use crate::ast::Document;
+use crate::version::Version;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A single security advisory entry from the feed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Advisory {
+    pub package: String,
+    pub namespace: String,
+    pub fixed_version: String,
+    pub cve_id: String,
+}
+
+/// A vulnerable package found by matching a [`Document`] against a set
+/// of [`Advisory`] entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub package: String,
+    pub installed_version: String,
+    pub cve_id: String,
+}
+
+/// Parses a JSON array of advisories, e.g. as downloaded from a
+/// security feed.
+pub fn load_advisories(source: &str) -> serde_json::Result<Vec<Advisory>> {
+    serde_json::from_str(source)
+}
+
+/// Reports every package in `document` that is vulnerable to one of
+/// `advisories`, i.e. whose installed version is strictly less than the
+/// advisory's fixed version. Only advisories whose `namespace` matches
+/// `document.namespace` are considered, so a dpkg document is never
+/// matched against apk advisories (or vice versa) using version
+/// semantics that don't apply to it.
+pub fn find_vulnerabilities(document: &Document, advisories: &[Advisory]) -> Vec<Match> {
+    let mut packages_by_name: HashMap<&str, Vec<&crate::ast::Package>> = HashMap::new();
+    for package in &document.packages {
+        packages_by_name
+            .entry(&package.name[..])
+            .or_default()
+            .push(package);
+    }
+
+    let mut matches = Vec::new();
+
+    let relevant = advisories
+        .iter()
+        .filter(|advisory| advisory.namespace == document.namespace);
+
+    for advisory in relevant {
+        let installed = match packages_by_name.get(&advisory.package[..]) {
+            Some(installed) => installed,
+            None => continue,
+        };
+
+        let fixed_version = Version::parse(&advisory.fixed_version);
+
+        for package in installed {
+            let installed_version = Version::parse(&package.version);
+
+            if installed_version < fixed_version {
+                matches.push(Match {
+                    package: package.name.clone(),
+                    installed_version: package.version.clone(),
+                    cve_id: advisory.cve_id.clone(),
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Package;
+
+    fn package(name: &str, version: &str) -> Package {
+        Package {
+            name: name.to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_load_advisories() {
+        let advisories = load_advisories(
+            r#"[{"package": "libssl1.0.0", "namespace": "debian:10", "fixed_version": "1.0.2-1", "cve_id": "CVE-2016-0000"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].package, "libssl1.0.0");
+    }
+
+    #[test]
+    fn test_find_vulnerabilities() {
+        let document = Document::new(
+            "debian".to_string(),
+            vec![
+                package("libssl1.0.0", "1.0.1-4ubuntu5.5"),
+                package("tcpd", "7.6.q-21"),
+            ],
+        );
+        let advisories = vec![Advisory {
+            package: "libssl1.0.0".to_string(),
+            namespace: "debian".to_string(),
+            fixed_version: "1.0.2-1".to_string(),
+            cve_id: "CVE-2016-0000".to_string(),
+        }];
+
+        let matches = find_vulnerabilities(&document, &advisories);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].package, "libssl1.0.0");
+        assert_eq!(matches[0].cve_id, "CVE-2016-0000");
+    }
+
+    #[test]
+    fn test_find_vulnerabilities_ignores_fixed_packages() {
+        let document = Document::new("debian".to_string(), vec![package("libssl1.0.0", "1.0.2-1")]);
+        let advisories = vec![Advisory {
+            package: "libssl1.0.0".to_string(),
+            namespace: "debian".to_string(),
+            fixed_version: "1.0.2-1".to_string(),
+            cve_id: "CVE-2016-0000".to_string(),
+        }];
+
+        assert!(find_vulnerabilities(&document, &advisories).is_empty());
+    }
+
+    #[test]
+    fn test_find_vulnerabilities_scopes_by_namespace() {
+        // Same package name, same (vulnerable-looking) version string, but
+        // one document is a debian package and the other is alpine's —
+        // an alpine advisory must never flag the debian package and
+        // vice versa.
+        let debian_document = Document::new("debian".to_string(), vec![package("openssl", "1.1.1")]);
+        let alpine_document = Document::new("alpine".to_string(), vec![package("openssl", "1.1.1")]);
+
+        let advisories = vec![
+            Advisory {
+                package: "openssl".to_string(),
+                namespace: "debian".to_string(),
+                fixed_version: "1.1.2".to_string(),
+                cve_id: "CVE-2021-0001".to_string(),
+            },
+            Advisory {
+                package: "openssl".to_string(),
+                namespace: "alpine".to_string(),
+                fixed_version: "1.1.1".to_string(),
+                cve_id: "CVE-2021-0002".to_string(),
+            },
+        ];
+
+        let debian_matches = find_vulnerabilities(&debian_document, &advisories);
+        assert_eq!(debian_matches.len(), 1);
+        assert_eq!(debian_matches[0].cve_id, "CVE-2021-0001");
+
+        let alpine_matches = find_vulnerabilities(&alpine_document, &advisories);
+        assert!(alpine_matches.is_empty());
+    }
+}